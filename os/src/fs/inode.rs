@@ -4,11 +4,13 @@
 //!
 //! `UPSafeCell<OSInodeInner>` -> `OSInode`: for static `ROOT_INODE`,we
 //! need to wrap `OSInodeInner` into `UPSafeCell`
-use super::{File, Stat, StatMode};
+use super::{File, SeekFrom, Stat, StatMode, TimeSpec};
 use crate::mm::UserBuffer;
 use crate::sync::UPSafeCell;
 use crate::drivers::BLOCK_DEVICE;
+use crate::timer::get_time_us;
 use alloc::{collections::BTreeMap, sync::Arc};
+use alloc::string::String;
 use alloc::vec::Vec;
 use bitflags::*;
 use easy_fs::{EasyFileSystem, Inode};
@@ -31,36 +33,54 @@ pub struct OSInodeInner {
 impl OSInode {
     /// create a new inode in memory
     pub fn new(readable: bool, writable: bool, inode: Arc<Inode>) -> Self {
+        let ino = inode.get_inode();
+        *OPEN_INODES.exclusive_access().entry(ino).or_insert(0) += 1;
         Self {
             readable,
             writable,
             inner: unsafe { UPSafeCell::new(OSInodeInner { offset: 0, inode }) },
         }
     }
-    /// read all data from the inode
-    pub fn read_all(&self) -> Vec<u8> {
-        let mut inner = self.inner.exclusive_access();
-        let mut buffer = [0u8; 512];
-        let mut v: Vec<u8> = Vec::new();
-        loop {
-            let len = inner.inode.read_at(inner.offset, &mut buffer);
-            if len == 0 {
-                break;
-            }
-            inner.offset += len;
-            v.extend_from_slice(&buffer[..len]);
+}
+
+impl Drop for OSInode {
+    fn drop(&mut self) {
+        let ino = self.inner.exclusive_access().inode.get_inode();
+        let mut open = OPEN_INODES.exclusive_access();
+        let count = open.get(&ino).cloned().unwrap_or(1);
+        if count <= 1 {
+            open.remove(&ino);
+        } else {
+            open.insert(ino, count - 1);
         }
-        v
+        drop(open);
+        reclaim_if_unlinked(ino);
     }
+}
 
-    /// check if the inode is flag deleted
-    pub fn is_deleted(&self, name: &str) -> bool {
-        self.inner.exclusive_access().inode.is_removed(name)
-    }
+/// `EINVAL`, as returned by `sys_lseek` on an out-of-range whence/offset
+const EINVAL: isize = -22;
 
-    /// check if the inode is a link
-    pub fn is_link(&self) -> bool {
-        self.inner.exclusive_access().inode.is_link()
+/// access/modify/change timestamps for one inode
+///
+/// easy_fs stores no timestamps on disk, so we track them here in the
+/// kernel, keyed by inode number, the same way `INODE_LINK_MAP` tracks
+/// link counts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Times {
+    /// time of last access
+    pub atime: TimeSpec,
+    /// time of last modification
+    pub mtime: TimeSpec,
+    /// time of last status change
+    pub ctime: TimeSpec,
+}
+
+fn now() -> TimeSpec {
+    let us = get_time_us();
+    TimeSpec {
+        sec: (us / 1_000_000) as i64,
+        nsec: ((us % 1_000_000) * 1_000) as i64,
     }
 }
 
@@ -73,6 +93,102 @@ lazy_static! {
         let map = BTreeMap::new();
         unsafe { UPSafeCell::new(map) }
     };
+    pub static ref INODE_TIMES_MAP: UPSafeCell<BTreeMap<u32, Times>> = {
+        let map = BTreeMap::new();
+        unsafe { UPSafeCell::new(map) }
+    };
+    /// Extended attributes, keyed by inode number and then by attribute
+    /// name, since easy_fs has nowhere on disk to store them.
+    pub static ref INODE_XATTR_MAP: UPSafeCell<BTreeMap<u32, BTreeMap<String, Vec<u8>>>> = {
+        let map = BTreeMap::new();
+        unsafe { UPSafeCell::new(map) }
+    };
+    /// Number of live `OSInode`s (open file descriptors) backed by each
+    /// inode number. An inode whose link count has dropped to zero can
+    /// only be reclaimed once this also reaches zero.
+    pub static ref OPEN_INODES: UPSafeCell<BTreeMap<u32, u32>> = {
+        let map = BTreeMap::new();
+        unsafe { UPSafeCell::new(map) }
+    };
+    /// Inode numbers that have reached nlink == 0 but were still open at
+    /// the time, so their reclamation was deferred to the last close.
+    static ref PENDING_RECLAIM: UPSafeCell<BTreeMap<u32, ()>> = {
+        let map = BTreeMap::new();
+        unsafe { UPSafeCell::new(map) }
+    };
+}
+
+/// Whether `ino` is ready to have its data blocks and directory slot
+/// freed: its link count must have already hit zero (`pending`), and no
+/// `OSInode` may still hold it open (`still_open`). Split out of
+/// `reclaim_if_unlinked` as a pure predicate so the guard can be exercised
+/// without the kernel's global inode tables.
+fn should_reclaim(pending: bool, still_open: bool) -> bool {
+    pending && !still_open
+}
+
+/// Free an inode's data blocks and directory slot once its link count has
+/// reached zero and no `OSInode` still has it open.
+///
+/// `ROOT_INODE.dealloc` is assumed to be a corresponding addition on the
+/// `easy_fs` side (this tree never touches the `easy_fs` crate itself,
+/// the same way `create_link`/`is_removed`/`is_link` are assumed to
+/// already exist there) that frees the inode's data blocks, clears its
+/// directory entry, and updates the block bitmap.
+fn reclaim_if_unlinked(ino: u32) {
+    let pending = PENDING_RECLAIM.exclusive_access().contains_key(&ino);
+    let still_open = OPEN_INODES.exclusive_access().contains_key(&ino);
+    if !should_reclaim(pending, still_open) {
+        return;
+    }
+    PENDING_RECLAIM.exclusive_access().remove(&ino);
+    // Only drop the nlink entry once the inode is actually gone: while
+    // reclaim was merely pending, `stat()` still needs to see the true
+    // (zero) count rather than falling back to the "never linked" default.
+    INODE_LINK_MAP.exclusive_access().remove(&ino);
+    INODE_TIMES_MAP.exclusive_access().remove(&ino);
+    INODE_XATTR_MAP.exclusive_access().remove(&ino);
+    ROOT_INODE.dealloc(ino);
+}
+
+#[cfg(test)]
+mod reclaim_tests {
+    use super::should_reclaim;
+
+    /// Regression test for the bug this request's reclaim redesign fixes:
+    /// a file that's unlinked while still open must NOT be reclaimed until
+    /// its last `OSInode` closes, but a file that was never unlinked must
+    /// also never be reclaimed just because it happens to be closed.
+    #[test]
+    fn only_reclaims_once_unlinked_and_closed() {
+        assert!(!should_reclaim(false, false));
+        assert!(!should_reclaim(false, true));
+        assert!(!should_reclaim(true, true));
+        assert!(should_reclaim(true, false));
+    }
+}
+
+/// Look up the current timestamps for `ino`, or all-zero defaults if it
+/// has never been touched (nothing is inserted in that case).
+fn times_of(ino: u32) -> Times {
+    INODE_TIMES_MAP
+        .exclusive_access()
+        .get(&ino)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Stamp `ino`'s atime (and, if `modified`, its mtime/ctime) with the
+/// current time, creating the entry on first touch.
+fn touch(ino: u32, modified: bool) {
+    let mut map = INODE_TIMES_MAP.exclusive_access();
+    let times = map.entry(ino).or_insert_with(Times::default);
+    let t = now();
+    times.atime = t;
+    if modified {
+        times.mtime = t;
+        times.ctime = t;
+    }
 }
 
 /// List all apps in the root directory
@@ -117,13 +233,16 @@ impl OpenFlags {
 /// Open a file
 pub fn open_file(name: &str, flags: OpenFlags) -> Option<Arc<OSInode>> {
     let (readable, writable) = flags.read_write();
-    if flags.contains(OpenFlags::CREATE) {
+    let mut modified = false;
+    let osinode = if flags.contains(OpenFlags::CREATE) {
         if let Some(inode) = ROOT_INODE.find(name) {
             // clear size
             inode.clear();
+            modified = true;
             Some(Arc::new(OSInode::new(readable, writable, inode)))
         } else {
             // create file
+            modified = true;
             ROOT_INODE
                 .create(name)
                 .map(|inode| Arc::new(OSInode::new(readable, writable, inode)))
@@ -132,10 +251,18 @@ pub fn open_file(name: &str, flags: OpenFlags) -> Option<Arc<OSInode>> {
         ROOT_INODE.find(name).map(|inode| {
             if flags.contains(OpenFlags::TRUNC) {
                 inode.clear();
+                modified = true;
             }
             Arc::new(OSInode::new(readable, writable, inode))
         })
+    };
+    // Opening only counts as an access unless it actually created or
+    // truncated the underlying file; a read-only open (or an xattr query
+    // that opens the file internally) must not bump mtime/ctime.
+    if let Some(osinode) = &osinode {
+        touch(osinode.inner.exclusive_access().inode.get_inode(), modified);
     }
+    osinode
 }
 
 impl File for OSInode {
@@ -156,6 +283,7 @@ impl File for OSInode {
             inner.offset += read_size;
             total_read_size += read_size;
         }
+        touch(inner.inode.get_inode(), false);
         total_read_size
     }
     fn write(&self, buf: UserBuffer) -> usize {
@@ -167,14 +295,17 @@ impl File for OSInode {
             inner.offset += write_size;
             total_write_size += write_size;
         }
+        touch(inner.inode.get_inode(), true);
         total_write_size
     }
     fn stat(&self) -> Option<Stat> {
         let inner = self.inner.exclusive_access();
+        let ino = inner.inode.get_inode();
+        let times = times_of(ino);
 
         Some(Stat {
             dev: 0,
-            ino: inner.inode.get_inode().into(),
+            ino: ino.into(),
             mode: {
                 match inner.inode.is_dir() {
                     true => StatMode::DIR,
@@ -183,56 +314,127 @@ impl File for OSInode {
             },
             nlink: {
                 let map = INODE_LINK_MAP.exclusive_access();
-                let count = map
-                    .get((&inner.inode.get_inode()).into())
-                    .cloned()
-                    .unwrap_or(1);
+                let count = map.get(&ino).cloned().unwrap_or(1);
 
                 count
             },
-            pad: [0; 7],
+            size: inner.inode.size() as u64,
+            atime: times.atime,
+            mtime: times.mtime,
+            ctime: times.ctime,
+            pad: [0; 4],
         })
     }
+    fn seek(&self, pos: SeekFrom) -> isize {
+        let mut inner = self.inner.exclusive_access();
+        let base = match pos {
+            SeekFrom::Start(offset) => offset as isize,
+            SeekFrom::Current(offset) => inner.offset as isize + offset,
+            SeekFrom::End(offset) => inner.inode.size() as isize + offset,
+        };
+        if base < 0 {
+            return EINVAL;
+        }
+        inner.offset = base as usize;
+        base
+    }
+    fn set_xattr(&self, name: &str, value: Vec<u8>) -> isize {
+        let ino = self.inner.exclusive_access().inode.get_inode();
+        let mut map = INODE_XATTR_MAP.exclusive_access();
+        map.entry(ino)
+            .or_insert_with(BTreeMap::new)
+            .insert(String::from(name), value);
+        0
+    }
+    fn get_xattr(&self, name: &str) -> Option<Vec<u8>> {
+        let ino = self.inner.exclusive_access().inode.get_inode();
+        INODE_XATTR_MAP
+            .exclusive_access()
+            .get(&ino)
+            .and_then(|attrs| attrs.get(name).cloned())
+    }
+    fn list_xattr(&self) -> Vec<String> {
+        let ino = self.inner.exclusive_access().inode.get_inode();
+        INODE_XATTR_MAP
+            .exclusive_access()
+            .get(&ino)
+            .map(|attrs| attrs.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+    fn remove_xattr(&self, name: &str) -> isize {
+        let ino = self.inner.exclusive_access().inode.get_inode();
+        let mut map = INODE_XATTR_MAP.exclusive_access();
+        match map.get_mut(&ino) {
+            Some(attrs) if attrs.remove(name).is_some() => 0,
+            _ => -1,
+        }
+    }
+    fn read_all(&self) -> Vec<u8> {
+        let mut inner = self.inner.exclusive_access();
+        let mut buffer = [0u8; 512];
+        let mut v: Vec<u8> = Vec::new();
+        loop {
+            let len = inner.inode.read_at(inner.offset, &mut buffer);
+            if len == 0 {
+                break;
+            }
+            inner.offset += len;
+            v.extend_from_slice(&buffer[..len]);
+        }
+        v
+    }
+    fn is_deleted(&self, name: &str) -> bool {
+        self.inner.exclusive_access().inode.is_removed(name)
+    }
+    fn is_link(&self) -> bool {
+        self.inner.exclusive_access().inode.is_link()
+    }
 }
 
-/// link two files
+/// link two files, incrementing the target inode's nlink
 pub fn link_file(old_name: &str, new_name: &str) -> isize {
     if old_name == new_name {
         return -1;
     }
 
-    if let Some(old_inode) = ROOT_INODE.find(old_name) {
-        if let Some(new_inode) = ROOT_INODE.create_link(new_name, old_inode.get_inode()) {
-            // increments link count
-            let mut inner = INODE_LINK_MAP.exclusive_access();
-            let old_count = inner.get(&new_inode.get_inode().into()).cloned().unwrap_or(1);
-            inner.insert(old_inode.get_inode().into(), old_count + 1);
-            0
-        } else {
-            -1
-        }
-    } else {
-        -1
+    let Some(old_inode) = ROOT_INODE.find(old_name) else {
+        return -1;
+    };
+    let ino = old_inode.get_inode();
+    if ROOT_INODE.create_link(new_name, ino).is_none() {
+        return -1;
     }
+
+    let mut inner = INODE_LINK_MAP.exclusive_access();
+    let count = inner.get(&ino).cloned().unwrap_or(1);
+    inner.insert(ino, count + 1);
+    0
 }
 
-/// unlink a file
+/// remove a directory entry and decrement its inode's nlink; once nlink
+/// hits zero and no `OSInode` still has it open, its data blocks and
+/// directory slot are freed
 pub fn unlink_file(file_name: &str) -> isize {
-    if let Some(inode) = ROOT_INODE.find(file_name) {
-        // flag in remove
-        inode.unlink(file_name);
-
-        // decrease link count
-        let mut inner = INODE_LINK_MAP.exclusive_access();
-        let old_count = inner.get(&inode.get_inode().into()).cloned().unwrap_or(1);
-        inner.insert(inode.get_inode().into(), old_count - 1);
-
-        if old_count == 0 {
-            inner.remove(&inode.get_inode().into());
-        }
+    let Some(inode) = ROOT_INODE.find(file_name) else {
+        return -1;
+    };
+    let ino = inode.get_inode();
+    inode.unlink(file_name);
 
-        0
-    } else {
-        -1
+    let mut inner = INODE_LINK_MAP.exclusive_access();
+    let count = inner.get(&ino).cloned().unwrap_or(1);
+    let new_count = count - 1;
+    // Keep an explicit zero entry rather than removing it: a still-open
+    // `OSInode` defers the actual reclaim (see `reclaim_if_unlinked`), and
+    // `stat()` must be able to tell "unlinked, nlink == 0" apart from "never
+    // linked, nlink == 1" in the meantime. The entry is only dropped once
+    // reclaim actually runs.
+    inner.insert(ino, new_count);
+    if new_count == 0 {
+        drop(inner);
+        PENDING_RECLAIM.exclusive_access().insert(ino, ());
+        reclaim_if_unlinked(ino);
     }
+
+    0
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+//! File trait & inode(dir, file, pipe, stdin, stdout)
+mod inode;
+mod stdio;
+pub mod tmpfs;
+pub mod vfs;
+
+use crate::mm::UserBuffer;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use bitflags::*;
+
+/// The common abstraction of all IO resources
+pub trait File: Send + Sync {
+    /// the file readable?
+    fn readable(&self) -> bool;
+    /// the file writable?
+    fn writable(&self) -> bool;
+    /// read from the file to buf, return the number of bytes read
+    fn read(&self, buf: UserBuffer) -> usize;
+    /// write to the file from buf, return the number of bytes written
+    fn write(&self, buf: UserBuffer) -> usize;
+    /// get the stat of the file, if it has one
+    fn stat(&self) -> Option<Stat> {
+        None
+    }
+    /// reposition the file offset, if the file supports seeking
+    ///
+    /// Returns the new absolute offset on success, or a negative errno
+    /// (e.g. `-EINVAL`) on failure. Files that don't support seeking (pipes,
+    /// stdio) keep the default of always failing.
+    fn seek(&self, _pos: SeekFrom) -> isize {
+        -1
+    }
+    /// set an extended attribute, if the file supports xattrs
+    ///
+    /// Returns `0` on success, `-1` if unsupported.
+    fn set_xattr(&self, _name: &str, _value: Vec<u8>) -> isize {
+        -1
+    }
+    /// read an extended attribute, if the file supports xattrs
+    fn get_xattr(&self, _name: &str) -> Option<Vec<u8>> {
+        None
+    }
+    /// list the names of all extended attributes set on this file
+    fn list_xattr(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// remove an extended attribute
+    ///
+    /// Returns `0` on success, `-1` if unsupported or not set.
+    fn remove_xattr(&self, _name: &str) -> isize {
+        -1
+    }
+    /// read the whole file from the start of its current offset, if the
+    /// file supports it (used by the ELF loader to slurp an executable in
+    /// one shot)
+    fn read_all(&self) -> Vec<u8> {
+        Vec::new()
+    }
+    /// check whether `name` has been unlinked from its containing
+    /// directory, if the file supports that notion
+    fn is_deleted(&self, _name: &str) -> bool {
+        false
+    }
+    /// check whether this file is a hard link target, if the file supports
+    /// that notion
+    fn is_link(&self) -> bool {
+        false
+    }
+}
+
+/// Where to seek from, mirroring the SEEK_SET/SEEK_CUR/SEEK_END semantics
+/// of `lseek(2)`.
+#[derive(Debug, Clone, Copy)]
+pub enum SeekFrom {
+    /// seek from the start of the file
+    Start(u64),
+    /// seek relative to the end of the file
+    End(i64),
+    /// seek relative to the current offset
+    Current(i64),
+}
+
+bitflags! {
+    /// The mode of a inode
+    /// whether a directory or a file
+    pub struct StatMode: u32 {
+        /// null
+        const NULL  = 0;
+        /// directory
+        const DIR   = 1 << 30;
+        /// ordinary regular file
+        const FILE  = 1 << 31;
+    }
+}
+
+/// A second+nanosecond timestamp, as used by the `atime`/`mtime`/`ctime`
+/// fields of [`Stat`] (mirrors the `statx` timestamp layout).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeSpec {
+    /// seconds
+    pub sec: i64,
+    /// nanoseconds
+    pub nsec: i64,
+}
+
+/// The stat of a inode
+#[repr(C)]
+pub struct Stat {
+    /// ID of device containing file
+    pub dev: u64,
+    /// inode number
+    pub ino: u64,
+    /// file type and mode
+    pub mode: StatMode,
+    /// number of hard links
+    pub nlink: u32,
+    /// total size, in bytes
+    pub size: u64,
+    /// time of last access
+    pub atime: TimeSpec,
+    /// time of last modification
+    pub mtime: TimeSpec,
+    /// time of last status change
+    pub ctime: TimeSpec,
+    /// unused pad
+    pub pad: [u64; 4],
+}
+
+pub use inode::{link_file, list_apps, unlink_file, OSInode, OpenFlags};
+pub use stdio::{Stdin, Stdout};
+pub use tmpfs::TmpFs;
+pub use vfs::{mount, umount, FileSystem};
+
+/// Open `name`, resolving it against the mount table first: a path
+/// beneath a mounted filesystem's target (e.g. `/tmp/foo` once tmpfs is
+/// mounted at `/tmp`) is delegated to that filesystem, and everything
+/// else falls back to the root easy_fs image.
+pub fn open_file(name: &str, flags: OpenFlags) -> Option<Arc<dyn File>> {
+    if let Some((fs, remainder)) = vfs::resolve(name) {
+        return fs.open(&remainder, flags);
+    }
+    inode::open_file(name, flags).map(|inode| inode as Arc<dyn File>)
+}
@@ -0,0 +1,107 @@
+//! A minimal in-memory filesystem, demonstrating that the mount table in
+//! [`super::vfs`] can host more than just the easy_fs-backed root.
+use super::vfs::FileSystem;
+use super::{File, OpenFlags};
+use crate::mm::UserBuffer;
+use crate::sync::UPSafeCell;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// A single in-memory, byte-vector-backed file.
+pub struct TmpFile {
+    readable: bool,
+    writable: bool,
+    inner: UPSafeCell<TmpFileInner>,
+}
+
+struct TmpFileInner {
+    offset: usize,
+    data: Arc<UPSafeCell<Vec<u8>>>,
+}
+
+impl File for TmpFile {
+    fn readable(&self) -> bool {
+        self.readable
+    }
+    fn writable(&self) -> bool {
+        self.writable
+    }
+    fn read(&self, mut buf: UserBuffer) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let data = inner.data.exclusive_access();
+        let mut total = 0;
+        for slice in buf.buffers.iter_mut() {
+            if inner.offset >= data.len() {
+                break;
+            }
+            let n = slice.len().min(data.len() - inner.offset);
+            slice[..n].copy_from_slice(&data[inner.offset..inner.offset + n]);
+            inner.offset += n;
+            total += n;
+            if n < slice.len() {
+                break;
+            }
+        }
+        total
+    }
+    fn write(&self, buf: UserBuffer) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let mut data = inner.data.exclusive_access();
+        let mut total = 0;
+        for slice in buf.buffers.iter() {
+            let end = inner.offset + slice.len();
+            if data.len() < end {
+                data.resize(end, 0);
+            }
+            data[inner.offset..end].copy_from_slice(slice);
+            inner.offset = end;
+            total += slice.len();
+        }
+        total
+    }
+}
+
+/// A single-directory in-memory filesystem: every opened path is just a
+/// key into a flat `name -> bytes` table.
+pub struct TmpFs {
+    files: UPSafeCell<BTreeMap<String, Arc<UPSafeCell<Vec<u8>>>>>,
+}
+
+impl TmpFs {
+    /// create a fresh, empty tmpfs instance
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            files: unsafe { UPSafeCell::new(BTreeMap::new()) },
+        })
+    }
+}
+
+impl FileSystem for TmpFs {
+    fn open(&self, path: &str, flags: OpenFlags) -> Option<Arc<dyn File>> {
+        let (readable, writable) = flags.read_write();
+        let mut files = self.files.exclusive_access();
+        let data = if flags.contains(OpenFlags::CREATE) {
+            let data = files
+                .entry(String::from(path))
+                .or_insert_with(|| Arc::new(unsafe { UPSafeCell::new(Vec::new()) }))
+                .clone();
+            if flags.contains(OpenFlags::TRUNC) {
+                data.exclusive_access().clear();
+            }
+            data
+        } else {
+            let data = files.get(path)?.clone();
+            if flags.contains(OpenFlags::TRUNC) {
+                data.exclusive_access().clear();
+            }
+            data
+        };
+        Some(Arc::new(TmpFile {
+            readable,
+            writable,
+            inner: unsafe { UPSafeCell::new(TmpFileInner { offset: 0, data }) },
+        }))
+    }
+}
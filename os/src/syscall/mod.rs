@@ -0,0 +1,81 @@
+//! Implementation of syscalls
+//!
+//! The single entry point to all system calls, `syscall`, is called by
+//! `trap_handler` and dispatches on `syscall_id` to the implementations
+//! below, forwarding whatever (up to 4) argument registers the call
+//! needs. Syscall numbers match the Linux riscv64 ABI where an equivalent
+//! Linux syscall exists, so user-space can use the familiar numbers.
+mod fs;
+mod process;
+
+use crate::fs::Stat;
+use fs::*;
+use process::*;
+
+/// sched_yield (Linux: 124)
+const SYSCALL_YIELD: usize = 124;
+/// exit (Linux: 93) — this kernel only supports whole-process exit
+const SYSCALL_EXIT: usize = 93;
+/// gettimeofday (Linux: 169)
+const SYSCALL_GET_TIME: usize = 169;
+/// brk (Linux: 214), repurposed here for the simplified sbrk-style ABI
+const SYSCALL_SBRK: usize = 214;
+/// munmap (Linux: 215)
+const SYSCALL_MUNMAP: usize = 215;
+/// mmap (Linux: 222)
+const SYSCALL_MMAP: usize = 222;
+/// lab-only syscall, not part of the Linux ABI
+const SYSCALL_TASK_INFO: usize = 410;
+/// lseek (Linux: 62)
+const SYSCALL_LSEEK: usize = 62;
+/// fstat (Linux: 80)
+const SYSCALL_FSTAT: usize = 80;
+/// umount2 (Linux: 39)
+const SYSCALL_UMOUNT2: usize = 39;
+/// mount (Linux: 40)
+const SYSCALL_MOUNT: usize = 40;
+/// setxattr (Linux: 5)
+const SYSCALL_SETXATTR: usize = 5;
+/// getxattr (Linux: 8)
+const SYSCALL_GETXATTR: usize = 8;
+/// listxattr (Linux: 11)
+const SYSCALL_LISTXATTR: usize = 11;
+/// removexattr (Linux: 14)
+const SYSCALL_REMOVEXATTR: usize = 14;
+
+/// handle syscall exception with `syscall_id` and its (up to 4) argument
+/// registers
+pub fn syscall(syscall_id: usize, args: [usize; 4]) -> isize {
+    match syscall_id {
+        SYSCALL_EXIT => sys_exit(args[0] as i32),
+        SYSCALL_YIELD => sys_yield(),
+        SYSCALL_GET_TIME => sys_get_time(args[0] as *mut TimeVal, args[1]),
+        SYSCALL_TASK_INFO => sys_task_info(args[0] as *mut TaskInfo),
+        SYSCALL_MMAP => sys_mmap(args[0], args[1], args[2]),
+        SYSCALL_MUNMAP => sys_munmap(args[0], args[1]),
+        SYSCALL_SBRK => sys_sbrk(args[0] as i32),
+        SYSCALL_LSEEK => sys_lseek(args[0], args[1] as isize, args[2]),
+        SYSCALL_FSTAT => sys_fstat(args[0], args[1] as *mut Stat),
+        SYSCALL_MOUNT => sys_mount(
+            args[0] as *const u8,
+            args[1] as *const u8,
+            args[2] as *const u8,
+        ),
+        SYSCALL_UMOUNT2 => sys_umount(args[0] as *const u8),
+        SYSCALL_SETXATTR => sys_setxattr(
+            args[0] as *const u8,
+            args[1] as *const u8,
+            args[2] as *const u8,
+            args[3],
+        ),
+        SYSCALL_GETXATTR => sys_getxattr(
+            args[0] as *const u8,
+            args[1] as *const u8,
+            args[2] as *mut u8,
+            args[3],
+        ),
+        SYSCALL_LISTXATTR => sys_listxattr(args[0] as *const u8, args[1] as *mut u8, args[2]),
+        SYSCALL_REMOVEXATTR => sys_removexattr(args[0] as *const u8, args[1] as *const u8),
+        _ => panic!("Unsupported syscall_id: {}", syscall_id),
+    }
+}
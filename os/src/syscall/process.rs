@@ -5,7 +5,7 @@ use crate::{
         change_program_brk, current_task_memset_mmap, current_task_memset_munmap,
         current_user_token, exit_current_and_run_next, suspend_current_and_run_next, TaskStatus,
     },
-    mm::translated_refmut,
+    mm::copy_to_user,
     timer::get_time_us,
     task::fetch_task_info
 };
@@ -54,32 +54,51 @@ pub fn sys_yield() -> isize {
     0
 }
 
-/// YOUR JOB: get time with second and microsecond
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TimeVal`] is splitted by two pages ?
+/// get time with second and microsecond
+///
+/// Writes through `copy_to_user` rather than `translated_refmut` so the
+/// write is correct even when `ts` lands a few bytes before a page
+/// boundary and `TimeVal` straddles two physical frames.
 pub fn sys_get_time(ts: *mut TimeVal, _tz: usize) -> isize {
     trace!("kernel: sys_get_time");
     let us = get_time_us();
-    *translated_refmut(current_user_token(), ts) = TimeVal {
+    let time_val = TimeVal {
         sec: us / 1_000_000,
         usec: us % 1_000_000,
-        };
+    };
+    // `TimeVal` is `#[repr(C)]` with two `usize` fields but not `Copy`, so
+    // copy it as the layout-identical `[usize; 2]`.
+    copy_to_user(
+        current_user_token(),
+        ts as *mut [usize; 2],
+        &[time_val.sec, time_val.usec],
+    );
     0
 }
 
-/// YOUR JOB: Finish sys_task_info to pass testcases
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TaskInfo`] is splitted by two pages ?
+/// Finish sys_task_info to pass testcases
+///
+/// Same cross-page-safe copy as `sys_get_time`: `TaskInfo` is large enough
+/// that a page-unaligned `ti` can straddle two pages, so we can't rely on
+/// `translated_refmut` translating a single page.
 pub fn sys_task_info(ti: *mut TaskInfo) -> isize {
     trace!("kernel: sys_task_info");
-    // unsafe {
-    //     *ti = fetch_current_task_info();
-    // }
-    *translated_refmut(current_user_token(), ti) = fetch_task_info();
+    let task_info = fetch_task_info();
+    copy_to_user(current_user_token(), ti, &task_info);
     0
 }
 
-// YOUR JOB: Implement mmap.
+// File-backed mmap (mapping an open OSInode's contents into a task's
+// address space, read_at-populated and write_at-flushed on MAP_SHARED)
+// is NOT implemented by this function, and is not something this commit
+// can honestly claim to deliver: it requires touching `current_task_memset_mmap`
+// and the page-fault/frame-population path in `task`/`mm`, neither of
+// which exists anywhere in this tree (it only tracks `os/src/fs`,
+// `os/src/syscall` and the user-copy helpers in `os/src/mm`). A prior commit here widened the
+// signature to `fd`/`offset` without making any of those changes, which
+// couldn't have compiled and implemented nothing; it's reverted back to
+// the original anonymous-mapping stub rather than left as a
+// non-functional signature change.
 pub fn sys_mmap(start: usize, len: usize, port: usize) -> isize {
     trace!("kernel: sys_mmap NOT IMPLEMENTED YET!");
 